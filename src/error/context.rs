@@ -0,0 +1,16 @@
+use core::fmt;
+
+/// A parent context associated with an error, describing the operation being
+/// performed when the error occurred, for example `"read u32"` or `"parse
+/// IPv4 header"`.
+///
+/// This is deliberately just a name. A `dyn Context` is boxed and walked
+/// generically via [`ContextStack`](super::ContextStack), so there's no way
+/// to recover a value attached to one without already knowing its concrete
+/// type — for that, attach the value directly to the error instead, as
+/// [`Reader::context_mut`](crate::reader::Reader::context_mut) does with the
+/// byte offset and marker [`Expected::provide`](super::Expected::provide)
+/// exposes.
+pub trait Context: fmt::Display + fmt::Debug + Send + Sync + 'static {}
+
+impl Context for &'static str {}
@@ -5,6 +5,12 @@ use crate::input::Input;
 use crate::utils::ByteCount;
 
 /// A catch-all error for all expected errors supported in this crate.
+///
+/// To get the concrete variant back out, prefer [`as_value`](Expected::as_value),
+/// [`as_valid`](Expected::as_valid) and [`as_length`](Expected::as_length) over
+/// [`dyn Details::downcast_ref`](super::Details::downcast_ref): `Expected<'i>`
+/// borrows from the input being parsed, so `'i` is essentially never
+/// `'static`, and `downcast_ref` only works for `Details<'static>`.
 #[derive(Debug, Clone)]
 pub enum Expected<'i> {
     /// An exact value was expected in a context.
@@ -29,21 +35,71 @@ impl<'i> Expected<'i> {
         }
     }
 
-    pub(crate) fn update_input(&mut self, input: &'i Input) {
+    pub(crate) fn update_input(&mut self, input: &'i Input<'i>) {
         match self {
             Self::Value(ref mut err) => err.update_input(input),
             Self::Valid(ref mut err) => err.update_input(input),
             Self::Length(ref mut err) => err.update_input(input),
         }
     }
+
+    /// Returns `true` if the error can be recovered from by backtracking to
+    /// try another alternative.
+    ///
+    /// This is always `true` unless the error was produced within a
+    /// [`Reader::cut`](crate::Reader::cut) scope, in which case an
+    /// alternative combinator must not try further branches and should
+    /// instead propagate the error as-is.
+    pub fn is_recoverable(&self) -> bool {
+        match self {
+            Self::Value(ref err) => err.is_recoverable(),
+            Self::Valid(ref err) => err.is_recoverable(),
+            Self::Length(ref err) => err.is_recoverable(),
+        }
+    }
+
+    pub(crate) fn set_recoverable(&mut self, recoverable: bool) {
+        match self {
+            Self::Value(ref mut err) => err.set_recoverable(recoverable),
+            Self::Valid(ref mut err) => err.set_recoverable(recoverable),
+            Self::Length(ref mut err) => err.set_recoverable(recoverable),
+        }
+    }
+
+    /// Returns the underlying [`ExpectedValue`] if this is an
+    /// [`Expected::Value`].
+    pub fn as_value(&self) -> Option<&ExpectedValue<'i>> {
+        match self {
+            Self::Value(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Returns the underlying [`ExpectedValid`] if this is an
+    /// [`Expected::Valid`].
+    pub fn as_valid(&self) -> Option<&ExpectedValid<'i>> {
+        match self {
+            Self::Valid(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Returns the underlying [`ExpectedLength`] if this is an
+    /// [`Expected::Length`].
+    pub fn as_length(&self) -> Option<&ExpectedLength<'i>> {
+        match self {
+            Self::Length(err) => Some(err),
+            _ => None,
+        }
+    }
 }
 
 impl<'i> ErrorDetails<'i> for Expected<'i> {
-    fn input(&self) -> &'i Input {
+    fn input(&self) -> &'i Input<'i> {
         self.details().input()
     }
 
-    fn span(&self) -> &'i Input {
+    fn span(&self) -> &'i Input<'i> {
         self.details().span()
     }
 
@@ -51,11 +107,11 @@ impl<'i> ErrorDetails<'i> for Expected<'i> {
         self.details().context()
     }
 
-    fn found_value(&self) -> Option<&Input> {
+    fn found_value(&self) -> Option<&Input<'i>> {
         self.details().found_value()
     }
 
-    fn expected_value(&self) -> Option<&Input> {
+    fn expected_value(&self) -> Option<&Input<'i>> {
         self.details().expected_value()
     }
 
@@ -88,42 +144,96 @@ impl<'i> From<ExpectedLength<'i>> for Expected<'i> {
 
 impl_error!(Expected);
 
+#[cfg(all(feature = "std", feature = "unstable-provide-api"))]
+impl<'i> Expected<'i> {
+    /// Provides structured values attached to this error to `request`, so
+    /// downstream error-reporting layers can recover them with
+    /// [`std::error::request_ref`] instead of parsing the display output.
+    ///
+    /// Currently exposes the byte offset and custom marker attached via
+    /// [`Reader::context_mut`](crate::Reader::context_mut), if any.
+    pub(crate) fn provide<'a>(&'a self, request: &mut std::error::Request<'a>) {
+        let (offset, marker) = match self {
+            Self::Value(err) => (&err.offset, &err.marker),
+            Self::Valid(err) => (&err.offset, &err.marker),
+            Self::Length(err) => (&err.offset, &err.marker),
+        };
+        if let Some(offset) = offset {
+            request.provide_ref::<usize>(offset);
+        }
+        if let Some(marker) = marker {
+            request.provide_ref::<&'static str>(marker);
+        }
+    }
+
+    pub(crate) fn set_offset(&mut self, offset: usize) {
+        match self {
+            Self::Value(ref mut err) => err.offset = Some(offset),
+            Self::Valid(ref mut err) => err.offset = Some(offset),
+            Self::Length(ref mut err) => err.offset = Some(offset),
+        }
+    }
+
+    pub(crate) fn set_marker(&mut self, marker: &'static str) {
+        match self {
+            Self::Value(ref mut err) => err.marker = Some(marker),
+            Self::Valid(ref mut err) => err.marker = Some(marker),
+            Self::Length(ref mut err) => err.marker = Some(marker),
+        }
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////////////
 // Expected value error
 
 /// An error representing a failed exact value requirement of [`Input`].
 #[derive(Debug, Clone)]
 pub struct ExpectedValue<'i> {
-    pub(crate) value: &'i Input,
-    pub(crate) span: &'i Input,
-    pub(crate) input: &'i Input,
+    pub(crate) value: &'i Input<'i>,
+    pub(crate) span: &'i Input<'i>,
+    pub(crate) input: &'i Input<'i>,
     pub(crate) operation: &'static str,
+    pub(crate) recoverable: bool,
+    #[cfg(all(feature = "std", feature = "unstable-provide-api"))]
+    pub(crate) offset: Option<usize>,
+    #[cfg(all(feature = "std", feature = "unstable-provide-api"))]
+    pub(crate) marker: Option<&'static str>,
 }
 
 impl<'i> ExpectedValue<'i> {
     /// The [`Input`] value that was expected.
-    pub fn expected(&self) -> &Input {
+    pub fn expected(&self) -> &Input<'i> {
         self.value
     }
 
+    /// Returns `true` if the error can be recovered from by backtracking to
+    /// try another alternative.
+    pub fn is_recoverable(&self) -> bool {
+        self.recoverable
+    }
+
     /// Returns an `ErrorDisplay` for formatting.
     pub fn display(&self) -> ErrorDisplay<&Self> {
         ErrorDisplay::new(self)
     }
 
-    pub(crate) fn update_input(&mut self, input: &'i Input) {
+    pub(crate) fn update_input(&mut self, input: &'i Input<'i>) {
         if self.input.is_within(input) {
             self.input = input;
         }
     }
+
+    pub(crate) fn set_recoverable(&mut self, recoverable: bool) {
+        self.recoverable = recoverable;
+    }
 }
 
 impl<'i> ErrorDetails<'i> for ExpectedValue<'i> {
-    fn input(&self) -> &'i Input {
+    fn input(&self) -> &'i Input<'i> {
         self.input
     }
 
-    fn span(&self) -> &'i Input {
+    fn span(&self) -> &'i Input<'i> {
         self.span
     }
 
@@ -131,11 +241,11 @@ impl<'i> ErrorDetails<'i> for ExpectedValue<'i> {
         &self.operation
     }
 
-    fn found_value(&self) -> Option<&Input> {
+    fn found_value(&self) -> Option<&Input<'i>> {
         Some(self.input)
     }
 
-    fn expected_value(&self) -> Option<&Input> {
+    fn expected_value(&self) -> Option<&Input<'i>> {
         Some(self.value)
     }
 
@@ -144,6 +254,11 @@ impl<'i> ErrorDetails<'i> for ExpectedValue<'i> {
     }
 
     fn retry_requirement(&self) -> Option<RetryRequirement> {
+        // Bound input is known to be complete, so a shortfall here can never
+        // be resolved by retrying with more bytes.
+        if self.input.is_bound() {
+            return None;
+        }
         let needed = self.value.len();
         let had = self.span().len();
         RetryRequirement::from_had_and_needed(had, needed)
@@ -160,9 +275,14 @@ impl_error!(ExpectedValue);
 pub struct ExpectedLength<'i> {
     pub(crate) min: usize,
     pub(crate) max: Option<usize>,
-    pub(crate) span: &'i Input,
-    pub(crate) input: &'i Input,
+    pub(crate) span: &'i Input<'i>,
+    pub(crate) input: &'i Input<'i>,
     pub(crate) operation: &'static str,
+    pub(crate) recoverable: bool,
+    #[cfg(all(feature = "std", feature = "unstable-provide-api"))]
+    pub(crate) offset: Option<usize>,
+    #[cfg(all(feature = "std", feature = "unstable-provide-api"))]
+    pub(crate) marker: Option<&'static str>,
 }
 
 impl<'i> ExpectedLength<'i> {
@@ -205,24 +325,34 @@ impl<'i> ExpectedLength<'i> {
         }
     }
 
+    /// Returns `true` if the error can be recovered from by backtracking to
+    /// try another alternative.
+    pub fn is_recoverable(&self) -> bool {
+        self.recoverable
+    }
+
     /// Returns an `ErrorDisplay` for formatting.
     pub fn display(&self) -> ErrorDisplay<&Self> {
         ErrorDisplay::new(self)
     }
 
-    pub(crate) fn update_input(&mut self, input: &'i Input) {
+    pub(crate) fn update_input(&mut self, input: &'i Input<'i>) {
         if self.input.is_within(input) {
             self.input = input;
         }
     }
+
+    pub(crate) fn set_recoverable(&mut self, recoverable: bool) {
+        self.recoverable = recoverable;
+    }
 }
 
 impl<'i> ErrorDetails<'i> for ExpectedLength<'i> {
-    fn input(&self) -> &'i Input {
+    fn input(&self) -> &'i Input<'i> {
         self.input
     }
 
-    fn span(&self) -> &'i Input {
+    fn span(&self) -> &'i Input<'i> {
         self.span
     }
 
@@ -230,11 +360,11 @@ impl<'i> ErrorDetails<'i> for ExpectedLength<'i> {
         &self.operation
     }
 
-    fn found_value(&self) -> Option<&Input> {
+    fn found_value(&self) -> Option<&Input<'i>> {
         Some(self.input)
     }
 
-    fn expected_value(&self) -> Option<&Input> {
+    fn expected_value(&self) -> Option<&Input<'i>> {
         None
     }
 
@@ -255,7 +385,9 @@ impl<'i> ErrorDetails<'i> for ExpectedLength<'i> {
     }
 
     fn retry_requirement(&self) -> Option<RetryRequirement> {
-        if self.is_fatal() {
+        // Bound input is known to be complete, so a shortfall here can never
+        // be resolved by retrying with more bytes.
+        if self.is_fatal() || self.input.is_bound() {
             None
         } else {
             let had = self.span().len();
@@ -273,32 +405,47 @@ impl_error!(ExpectedLength);
 /// An error representing a failed requirement for a valid [`Input`].
 #[derive(Debug, Clone)]
 pub struct ExpectedValid<'i> {
-    pub(crate) span: &'i Input,
-    pub(crate) input: &'i Input,
+    pub(crate) span: &'i Input<'i>,
+    pub(crate) input: &'i Input<'i>,
     pub(crate) operation: &'static str,
     pub(crate) expected: &'static str,
     pub(crate) retry_requirement: Option<RetryRequirement>,
+    pub(crate) recoverable: bool,
+    #[cfg(all(feature = "std", feature = "unstable-provide-api"))]
+    pub(crate) offset: Option<usize>,
+    #[cfg(all(feature = "std", feature = "unstable-provide-api"))]
+    pub(crate) marker: Option<&'static str>,
 }
 
 impl<'i> ExpectedValid<'i> {
+    /// Returns `true` if the error can be recovered from by backtracking to
+    /// try another alternative.
+    pub fn is_recoverable(&self) -> bool {
+        self.recoverable
+    }
+
     /// Returns an `ErrorDisplay` for formatting.
     pub fn display(&self) -> ErrorDisplay<&Self> {
         ErrorDisplay::new(self)
     }
 
-    pub(crate) fn update_input(&mut self, input: &'i Input) {
+    pub(crate) fn update_input(&mut self, input: &'i Input<'i>) {
         if self.input.is_within(input) {
             self.input = input;
         }
     }
+
+    pub(crate) fn set_recoverable(&mut self, recoverable: bool) {
+        self.recoverable = recoverable;
+    }
 }
 
 impl<'i> ErrorDetails<'i> for ExpectedValid<'i> {
-    fn input(&self) -> &'i Input {
+    fn input(&self) -> &'i Input<'i> {
         self.input
     }
 
-    fn span(&self) -> &'i Input {
+    fn span(&self) -> &'i Input<'i> {
         self.span
     }
 
@@ -306,11 +453,11 @@ impl<'i> ErrorDetails<'i> for ExpectedValid<'i> {
         &self.operation
     }
 
-    fn found_value(&self) -> Option<&Input> {
+    fn found_value(&self) -> Option<&Input<'i>> {
         Some(self.input)
     }
 
-    fn expected_value(&self) -> Option<&Input> {
+    fn expected_value(&self) -> Option<&Input<'i>> {
         None
     }
 
@@ -319,8 +466,56 @@ impl<'i> ErrorDetails<'i> for ExpectedValid<'i> {
     }
 
     fn retry_requirement(&self) -> Option<RetryRequirement> {
-        self.retry_requirement
+        // Bound input is known to be complete, so a shortfall here can never
+        // be resolved by retrying with more bytes.
+        if self.input.is_bound() {
+            None
+        } else {
+            self.retry_requirement
+        }
     }
 }
 
-impl_error!(ExpectedValid);
\ No newline at end of file
+impl_error!(ExpectedValid);
+
+#[cfg(all(test, feature = "std", feature = "unstable-provide-api"))]
+mod tests {
+    use super::{Expected, ExpectedValue};
+    use crate::input::Input;
+
+    #[test]
+    fn provide_exposes_offset_and_marker() {
+        let input = Input::new(b"abc");
+        let err = Expected::Value(ExpectedValue {
+            value: &input,
+            span: &input,
+            input: &input,
+            operation: "read byte",
+            recoverable: true,
+            offset: Some(3),
+            marker: Some("custom marker"),
+        });
+
+        assert_eq!(std::error::request_ref::<usize>(&err), Some(&3));
+        assert_eq!(
+            std::error::request_ref::<&'static str>(&err),
+            Some(&"custom marker")
+        );
+    }
+
+    #[test]
+    fn provide_is_none_when_nothing_attached() {
+        let input = Input::new(b"abc");
+        let err = Expected::Value(ExpectedValue {
+            value: &input,
+            span: &input,
+            input: &input,
+            operation: "read byte",
+            recoverable: true,
+            offset: None,
+            marker: None,
+        });
+
+        assert_eq!(std::error::request_ref::<usize>(&err), None);
+    }
+}
\ No newline at end of file
@@ -1,9 +1,15 @@
+use core::any::TypeId;
 use core::fmt;
 
 use crate::input::Input;
 
 use super::{Context, ContextStack, ExpectedLength, ExpectedValid, ExpectedValue};
 
+mod private {
+    #[derive(Debug)]
+    pub struct Internal;
+}
+
 /// Convenience trait requiring [`FromContext`], [`FromExpected`].
 pub trait Error<'i>: FromContext<'i> + FromExpected<'i> {}
 
@@ -39,6 +45,20 @@ impl<'i, T> FromExpected<'i> for T where
 /// concrete type [`Invalid`] and all of the computations around verbose
 /// erroring will be removed in compilation.
 ///
+/// # Downcasting
+///
+/// [`dyn Details::downcast_ref`](dyn Details::downcast_ref) only works for
+/// `Details<'static>`, because `TypeId` can only name `'static` types. Every
+/// error type this crate provides (`Expected<'i>`, `ExpectedLength<'i>`,
+/// `ExpectedValid<'i>`, `ExpectedValue<'i>`, ...) borrows from the input
+/// being parsed, so `'i` is essentially never `'static` in practice. A call
+/// to `downcast_ref` on a `dyn Details<'i>` whose `'i` is not `'static` does
+/// not compile, because the method only exists on `dyn Details<'static>`. If you hold a concrete
+/// [`Expected`](super::Expected), prefer its [`as_value`](super::Expected::as_value),
+/// [`as_valid`](super::Expected::as_valid) and
+/// [`as_length`](super::Expected::as_length) accessors instead, which work
+/// for any `'i`.
+///
 /// [`Invalid`]: crate::error::Invalid
 pub trait Details<'i> {
     /// The input in its entirety that was being processed when an error
@@ -67,4 +87,137 @@ pub trait Details<'i> {
     /// The walkable [`ContextStack`] to the original context around the error
     /// that occurred.
     fn context_stack(&self) -> &dyn ContextStack;
+
+    /// Returns `true` if the error can be recovered from by backtracking to
+    /// try another alternative.
+    ///
+    /// Errors produced within a [`Reader::cut`](crate::Reader::cut) scope
+    /// return `false` here, signalling to an alternative combinator that it
+    /// must not try further branches.
+    fn is_recoverable(&self) -> bool;
+
+    /// Returns the `TypeId` of the concrete underlying error.
+    ///
+    /// This is a private implementation detail of [`downcast_ref`] and
+    /// [`is`](dyn Details::is), following the same `Internal`-guarded hook
+    /// [`std::error::Error`] uses for its own downcasting. Only types whose
+    /// borrowed data is `'static` (i.e. `'i == 'static`) can be named here,
+    /// mirroring the `'static` bound `std::error::Error::downcast_ref`
+    /// requires.
+    ///
+    /// [`downcast_ref`]: dyn Details::downcast_ref
+    #[doc(hidden)]
+    fn __type_id(&self, _: private::Internal) -> TypeId
+    where
+        Self: 'static,
+    {
+        TypeId::of::<Self>()
+    }
+}
+
+impl dyn Details<'static> {
+    /// Returns `true` if the underlying error is of type `T`.
+    ///
+    /// Only usable where the error's borrowed input happens to be `'static`
+    /// — see the "Downcasting" section on [`Details`]. Errors that borrow
+    /// from non-`'static` input cannot be reached through this method at all.
+    pub fn is<T: Details<'static> + 'static>(&self) -> bool {
+        self.downcast_ref::<T>().is_some()
+    }
+
+    /// Attempts to downcast `&dyn Details<'static>` to a concrete type `T`.
+    ///
+    /// Only usable where the error's borrowed input happens to be `'static`
+    /// — see the "Downcasting" section on [`Details`]. Errors that borrow
+    /// from non-`'static` input cannot be reached through this method at all.
+    pub fn downcast_ref<T: Details<'static> + 'static>(&self) -> Option<&T> {
+        if self.__type_id(private::Internal) == TypeId::of::<T>() {
+            // SAFETY: we just checked that `T` is the concrete type stored
+            // behind this trait object via `TypeId`.
+            Some(unsafe { &*(self as *const dyn Details<'static> as *const T) })
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt;
+
+    use super::Details;
+    use crate::error::ContextStack;
+    use crate::input::Input;
+
+    struct DummyError;
+
+    impl Details<'static> for DummyError {
+        fn input(&self) -> Input<'static> {
+            Input::new(b"")
+        }
+
+        fn span(&self) -> Input<'static> {
+            Input::new(b"")
+        }
+
+        fn expected(&self) -> Option<Input<'_>> {
+            None
+        }
+
+        fn description(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("dummy error")
+        }
+
+        fn context_stack(&self) -> &dyn ContextStack {
+            unimplemented!("not exercised by the downcast tests")
+        }
+
+        fn is_recoverable(&self) -> bool {
+            true
+        }
+    }
+
+    struct OtherError;
+
+    impl Details<'static> for OtherError {
+        fn input(&self) -> Input<'static> {
+            Input::new(b"")
+        }
+
+        fn span(&self) -> Input<'static> {
+            Input::new(b"")
+        }
+
+        fn expected(&self) -> Option<Input<'_>> {
+            None
+        }
+
+        fn description(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("other error")
+        }
+
+        fn context_stack(&self) -> &dyn ContextStack {
+            unimplemented!("not exercised by the downcast tests")
+        }
+
+        fn is_recoverable(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn downcast_ref_matches_the_concrete_type() {
+        let err = DummyError;
+        let dyn_err: &dyn Details<'static> = &err;
+        assert!(dyn_err.is::<DummyError>());
+        assert!(dyn_err.downcast_ref::<DummyError>().is_some());
+    }
+
+    #[test]
+    fn downcast_ref_rejects_a_mismatched_type() {
+        let err = DummyError;
+        let dyn_err: &dyn Details<'static> = &err;
+        assert!(!dyn_err.is::<OtherError>());
+        assert!(dyn_err.downcast_ref::<OtherError>().is_none());
+    }
 }
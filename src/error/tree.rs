@@ -0,0 +1,259 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::error::{
+    Context, ErrorDisplay, ExpectedLength, ExpectedValid, ExpectedValue, FromContext,
+};
+use crate::input::Input;
+
+/// An error that records every failed alternative attempted while parsing, as
+/// a tree.
+///
+/// Unlike [`Expected`](super::Expected), which only ever keeps the single
+/// error that bubbled up, `TreeError` keeps every branch an alternative
+/// combinator tried, along with the context stack that led to each one. This
+/// makes it far more expensive to build and hold onto, but it turns a
+/// many-branch grammar failure into a readable "here is everything I tried"
+/// report instead of a single, possibly misleading, leaf error.
+pub struct TreeError<'i> {
+    node: TreeErrorNode<'i>,
+}
+
+enum TreeErrorNode<'i> {
+    Leaf(TreeErrorLeaf<'i>),
+    Stack {
+        context: Box<dyn Context>,
+        child: Box<TreeErrorNode<'i>>,
+    },
+    Alt(Vec<TreeErrorNode<'i>>),
+}
+
+enum TreeErrorLeaf<'i> {
+    Value(ExpectedValue<'i>),
+    Length(ExpectedLength<'i>),
+    Valid(ExpectedValid<'i>),
+}
+
+impl<'i> TreeError<'i> {
+    /// Returns an `ErrorDisplay` for formatting.
+    pub fn display(&self) -> ErrorDisplay<&Self> {
+        ErrorDisplay::new(self)
+    }
+
+    /// Merges two `TreeError`s as the two sides of an alternative.
+    ///
+    /// If either side is already an `Alt` node, the other is added to it
+    /// (appended if `self` is the `Alt`, prepended if `other` is) rather than
+    /// nesting `Alt`s inside each other.
+    pub(crate) fn merge(self, other: Self) -> Self {
+        let node = match (self.node, other.node) {
+            (TreeErrorNode::Alt(mut nodes), TreeErrorNode::Alt(other_nodes)) => {
+                nodes.extend(other_nodes);
+                TreeErrorNode::Alt(nodes)
+            }
+            (TreeErrorNode::Alt(mut nodes), other_node) => {
+                nodes.push(other_node);
+                TreeErrorNode::Alt(nodes)
+            }
+            (node, TreeErrorNode::Alt(mut other_nodes)) => {
+                other_nodes.insert(0, node);
+                TreeErrorNode::Alt(other_nodes)
+            }
+            (node, other_node) => TreeErrorNode::Alt(alloc::vec![node, other_node]),
+        };
+        Self { node }
+    }
+
+    fn fmt_node(
+        node: &TreeErrorNode<'_>,
+        f: &mut fmt::Formatter<'_>,
+        depth: usize,
+        debug: bool,
+    ) -> fmt::Result {
+        match node {
+            TreeErrorNode::Leaf(leaf) => Self::fmt_leaf(leaf, f, depth, debug),
+            TreeErrorNode::Stack { context, child } => {
+                Self::write_indent(f, depth)?;
+                writeln!(f, "in {}:", context)?;
+                Self::fmt_node(child, f, depth + 1, debug)
+            }
+            TreeErrorNode::Alt(nodes) => {
+                Self::write_indent(f, depth)?;
+                writeln!(f, "attempted {} alternative(s):", nodes.len())?;
+                for (i, node) in nodes.iter().enumerate() {
+                    Self::write_indent(f, depth + 1)?;
+                    writeln!(f, "{}.", i + 1)?;
+                    Self::fmt_node(node, f, depth + 2, debug)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn fmt_leaf(
+        leaf: &TreeErrorLeaf<'_>,
+        f: &mut fmt::Formatter<'_>,
+        depth: usize,
+        debug: bool,
+    ) -> fmt::Result {
+        Self::write_indent(f, depth)?;
+        if debug {
+            match leaf {
+                TreeErrorLeaf::Value(err) => writeln!(f, "{:?}", err),
+                TreeErrorLeaf::Length(err) => writeln!(f, "{:?}", err),
+                TreeErrorLeaf::Valid(err) => writeln!(f, "{:?}", err),
+            }
+        } else {
+            match leaf {
+                TreeErrorLeaf::Value(err) => writeln!(f, "{}", err.display()),
+                TreeErrorLeaf::Length(err) => writeln!(f, "{}", err.display()),
+                TreeErrorLeaf::Valid(err) => writeln!(f, "{}", err.display()),
+            }
+        }
+    }
+
+    fn write_indent(f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        for _ in 0..depth {
+            f.write_str("  ")?;
+        }
+        Ok(())
+    }
+}
+
+impl<'i> FromContext<'i> for TreeError<'i> {
+    fn from_context<C>(self, _input: Input<'i>, context: C) -> Self
+    where
+        C: Context,
+    {
+        Self {
+            node: TreeErrorNode::Stack {
+                context: Box::new(context),
+                child: Box::new(self.node),
+            },
+        }
+    }
+}
+
+impl<'i> From<ExpectedValue<'i>> for TreeError<'i> {
+    fn from(err: ExpectedValue<'i>) -> Self {
+        Self {
+            node: TreeErrorNode::Leaf(TreeErrorLeaf::Value(err)),
+        }
+    }
+}
+
+impl<'i> From<ExpectedLength<'i>> for TreeError<'i> {
+    fn from(err: ExpectedLength<'i>) -> Self {
+        Self {
+            node: TreeErrorNode::Leaf(TreeErrorLeaf::Length(err)),
+        }
+    }
+}
+
+impl<'i> From<ExpectedValid<'i>> for TreeError<'i> {
+    fn from(err: ExpectedValid<'i>) -> Self {
+        Self {
+            node: TreeErrorNode::Leaf(TreeErrorLeaf::Valid(err)),
+        }
+    }
+}
+
+// `TreeError` hand-rolls `Display`/`Debug` here rather than finishing with
+// `impl_error!` like the other error types in this module: `impl_error!`
+// formats through a single `ErrorDetails` (one input, one span, one
+// context), but a `TreeError` is a tree of every alternative that was
+// tried, each with its own leaf and context stack, so there's no single
+// `ErrorDetails` to delegate to. `display()` above still wraps this in
+// `ErrorDisplay` to match the calling convention used elsewhere.
+impl<'i> fmt::Display for TreeError<'i> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Self::fmt_node(&self.node, f, 0, false)
+    }
+}
+
+impl<'i> fmt::Debug for TreeError<'i> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Self::fmt_node(&self.node, f, 0, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+    use alloc::string::ToString;
+
+    use super::TreeError;
+    use crate::error::{ExpectedLength, FromContext};
+    use crate::input::Input;
+
+    fn length_err<'i>(input: &'i Input<'i>, operation: &'static str) -> TreeError<'i> {
+        TreeError::from(ExpectedLength {
+            min: 1,
+            max: None,
+            span: input,
+            input,
+            operation,
+            recoverable: true,
+            #[cfg(all(feature = "std", feature = "unstable-provide-api"))]
+            offset: None,
+            #[cfg(all(feature = "std", feature = "unstable-provide-api"))]
+            marker: None,
+        })
+    }
+
+    #[test]
+    fn merge_leaf_with_leaf_becomes_a_two_way_alt() {
+        let input = Input::new(b"a");
+        let merged = length_err(&input, "a").merge(length_err(&input, "b"));
+        assert!(merged.to_string().contains("attempted 2 alternative(s):"));
+    }
+
+    #[test]
+    fn merge_alt_with_leaf_appends_rather_than_nests() {
+        let input = Input::new(b"a");
+        let alt = length_err(&input, "a").merge(length_err(&input, "b"));
+        let merged = alt.merge(length_err(&input, "c"));
+        let rendered = merged.to_string();
+        assert_eq!(rendered.matches("attempted").count(), 1);
+        assert!(rendered.contains("attempted 3 alternative(s):"));
+    }
+
+    #[test]
+    fn merge_leaf_with_alt_prepends_rather_than_nests() {
+        let input = Input::new(b"a");
+        let alt = length_err(&input, "b").merge(length_err(&input, "c"));
+        let merged = length_err(&input, "a").merge(alt);
+        let rendered = merged.to_string();
+        assert_eq!(rendered.matches("attempted").count(), 1);
+        assert!(rendered.contains("attempted 3 alternative(s):"));
+    }
+
+    #[test]
+    fn merge_alt_with_alt_flattens_into_one_alt() {
+        let input = Input::new(b"a");
+        let left = length_err(&input, "a").merge(length_err(&input, "b"));
+        let right = length_err(&input, "c").merge(length_err(&input, "d"));
+        let merged = left.merge(right);
+        let rendered = merged.to_string();
+        assert_eq!(rendered.matches("attempted").count(), 1);
+        assert!(rendered.contains("attempted 4 alternative(s):"));
+    }
+
+    #[test]
+    fn display_indents_nested_stack_context() {
+        let input = Input::new(b"a");
+        let err = length_err(&input, "read byte").from_context(input, "parse header");
+        let rendered = err.to_string();
+        let mut lines = rendered.lines();
+        assert_eq!(lines.next(), Some("in parse header:"));
+        assert!(lines.next().unwrap().starts_with("  "));
+    }
+
+    #[test]
+    fn debug_renders_leaves_with_debug_format() {
+        let input = Input::new(b"a");
+        let rendered = format!("{:?}", length_err(&input, "read byte"));
+        assert!(rendered.contains("ExpectedLength"));
+    }
+}
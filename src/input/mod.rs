@@ -0,0 +1,116 @@
+use core::fmt;
+
+/// A span of bytes being parsed, along with whether it is known to be
+/// complete.
+///
+/// Input created via [`Input::streaming`] (or [`Input::new`]) signals that
+/// more bytes may arrive later, so running out of bytes partway through a
+/// read is a retryable shortfall. Input created via [`Input::complete`]
+/// signals that `bytes` is the entirety of the data to be parsed, so running
+/// out of bytes is a fatal error: there is nothing more to retry with.
+#[derive(Clone, Copy)]
+pub struct Input<'i> {
+    bytes: &'i [u8],
+    bound: bool,
+}
+
+impl<'i> Input<'i> {
+    /// Constructs a new `Input` from a byte slice.
+    ///
+    /// The input starts out streaming (see [`Input::streaming`]); call
+    /// [`Input::complete`] if `bytes` holds the entirety of the data to be
+    /// parsed.
+    pub fn new(bytes: &'i [u8]) -> Self {
+        Self {
+            bytes,
+            bound: false,
+        }
+    }
+
+    /// Marks this input as complete.
+    ///
+    /// The caller is asserting `bytes` holds the entirety of the data to be
+    /// parsed, so a shortfall while reading it can never be resolved by
+    /// retrying with more bytes.
+    pub fn complete(self) -> Self {
+        Self {
+            bound: true,
+            ..self
+        }
+    }
+
+    /// Marks this input as streaming.
+    ///
+    /// More bytes may arrive later, so a shortfall while reading it is a
+    /// signal to retry rather than a fatal error. This is the default for
+    /// [`Input::new`].
+    pub fn streaming(self) -> Self {
+        Self {
+            bound: false,
+            ..self
+        }
+    }
+
+    /// Returns `true` if this input is [`complete`](Input::complete), i.e. is
+    /// known to hold the entirety of the data to be parsed.
+    pub fn is_bound(&self) -> bool {
+        self.bound
+    }
+
+    /// Returns the number of bytes in this input.
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns `true` if this input contains no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Returns `true` if `self` is a sub-slice of `other`, i.e. `other` is
+    /// known to describe at least as much of the underlying buffer.
+    pub(crate) fn is_within(&self, other: &Input<'i>) -> bool {
+        let self_start = self.bytes.as_ptr() as usize;
+        let self_end = self_start + self.bytes.len();
+        let other_start = other.bytes.as_ptr() as usize;
+        let other_end = other_start + other.bytes.len();
+        self_start >= other_start && self_end <= other_end
+    }
+
+    /// Returns the underlying byte slice.
+    ///
+    /// Named to make call sites stand out, so a reader can audit where raw
+    /// bytes escape the safety the rest of this crate provides.
+    pub fn as_dangerous(&self) -> &'i [u8] {
+        self.bytes
+    }
+}
+
+impl fmt::Debug for Input<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Input")
+            .field("bytes", &self.bytes)
+            .field("bound", &self.bound)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Input;
+
+    #[test]
+    fn streaming_input_is_not_bound() {
+        let input = Input::new(b"abc");
+        assert!(!input.is_bound());
+        assert!(!input.streaming().is_bound());
+    }
+
+    #[test]
+    fn complete_input_is_bound() {
+        let input = Input::new(b"abc").complete();
+        assert!(input.is_bound());
+        // Switching back to streaming clears the bound flag.
+        assert!(!input.streaming().is_bound());
+    }
+}
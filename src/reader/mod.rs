@@ -0,0 +1,154 @@
+use crate::error::Expected;
+use crate::input::Input;
+
+/// Reads structured values out of an [`Input`], producing [`Expected`]
+/// errors when a read fails.
+pub struct Reader<'i> {
+    start: Input<'i>,
+    input: Input<'i>,
+}
+
+impl<'i> Reader<'i> {
+    /// Constructs a new `Reader` over `input`.
+    pub fn new(input: Input<'i>) -> Self {
+        Self {
+            start: input,
+            input,
+        }
+    }
+
+    /// Returns the remaining input still to be read.
+    pub fn input(&self) -> Input<'i> {
+        self.input
+    }
+
+    /// Runs `f`, and if it fails, marks the resulting error as unrecoverable.
+    ///
+    /// This "cuts" off backtracking: once `f` has committed to a branch (for
+    /// example, because it read an unambiguous tag or delimiter), a later
+    /// failure within `f` should be reported as-is rather than masked by
+    /// [`Reader::or`] silently trying the next alternative.
+    pub fn cut<F, T>(&mut self, f: F) -> Result<T, Expected<'i>>
+    where
+        F: FnOnce(&mut Self) -> Result<T, Expected<'i>>,
+    {
+        f(self).map_err(|mut err| {
+            err.set_recoverable(false);
+            err
+        })
+    }
+
+    /// Tries `f`, backtracking to `g` if `f` fails with a
+    /// [recoverable](Expected::is_recoverable) error.
+    ///
+    /// If `f`'s error is not recoverable (for example, because it was
+    /// produced within a [`Reader::cut`] scope), `g` is never tried and `f`'s
+    /// error is returned as-is.
+    pub fn or<F, G, T>(&mut self, f: F, g: G) -> Result<T, Expected<'i>>
+    where
+        F: FnOnce(&mut Self) -> Result<T, Expected<'i>>,
+        G: FnOnce(&mut Self) -> Result<T, Expected<'i>>,
+    {
+        let before = self.input;
+        match f(self) {
+            Ok(value) => Ok(value),
+            Err(err) if err.is_recoverable() => {
+                self.input = before;
+                g(self)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Returns the number of bytes consumed since this `Reader` was
+    /// constructed.
+    #[cfg(all(feature = "std", feature = "unstable-provide-api"))]
+    fn offset(&self) -> usize {
+        self.start.len() - self.input.len()
+    }
+
+    /// Runs `f`, and if it fails, attaches the reader's current byte offset
+    /// and `marker` to the resulting error, so they can be recovered later
+    /// with [`std::error::request_ref`] (see [`Expected::provide`]).
+    #[cfg(all(feature = "std", feature = "unstable-provide-api"))]
+    pub fn context_mut<F, T>(&mut self, marker: &'static str, f: F) -> Result<T, Expected<'i>>
+    where
+        F: FnOnce(&mut Self) -> Result<T, Expected<'i>>,
+    {
+        let offset = self.offset();
+        f(self).map_err(|mut err| {
+            err.set_offset(offset);
+            err.set_marker(marker);
+            err
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Reader;
+    use crate::error::{Expected, ExpectedLength};
+    use crate::input::Input;
+
+    fn length_error<'i>(input: &'i Input<'i>, operation: &'static str) -> Expected<'i> {
+        Expected::Length(ExpectedLength {
+            min: 1,
+            max: None,
+            span: input,
+            input,
+            operation,
+            recoverable: true,
+            #[cfg(all(feature = "std", feature = "unstable-provide-api"))]
+            offset: None,
+            #[cfg(all(feature = "std", feature = "unstable-provide-api"))]
+            marker: None,
+        })
+    }
+
+    #[test]
+    fn or_backtracks_on_recoverable_error() {
+        let input = Input::new(b"a");
+        let mut reader = Reader::new(input);
+        let result = reader.or(|_r| Err(length_error(&input, "first branch")), |_r| Ok(1u8));
+        assert_eq!(result.unwrap(), 1u8);
+    }
+
+    #[test]
+    fn cut_marks_error_as_unrecoverable() {
+        let input = Input::new(b"a");
+        let mut reader = Reader::new(input);
+        let result = reader.cut(|_r| Err(length_error(&input, "read byte")));
+        let err = result.unwrap_err();
+        assert!(!err.is_recoverable());
+    }
+
+    #[test]
+    fn or_does_not_backtrack_past_a_cut() {
+        let input = Input::new(b"a");
+        let mut reader = Reader::new(input);
+        let result = reader.or(
+            |r| r.cut(|_r| Err(length_error(&input, "first branch"))),
+            |_r| Ok(1u8),
+        );
+        assert!(result.is_err());
+    }
+
+    #[cfg(all(feature = "std", feature = "unstable-provide-api"))]
+    #[test]
+    fn context_mut_attaches_offset_and_marker() {
+        let input = Input::new(b"ab");
+        let mut reader = Reader::new(input);
+        reader.input = Input::new(b"b");
+
+        let result = reader.context_mut("custom marker", |_r| {
+            Err(length_error(&input, "read byte"))
+        });
+        let err = result.unwrap_err();
+
+        assert_eq!(std::error::request_ref::<usize>(&err), Some(&1));
+        assert_eq!(
+            std::error::request_ref::<&'static str>(&err),
+            Some(&"custom marker")
+        );
+    }
+}